@@ -0,0 +1,233 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Derive macros for the Apache Iceberg Rust writer API.
+//!
+//! This crate is not meant to be used directly; it is re-exported by the `iceberg` crate as
+//! `iceberg::writer::IcebergRecordWriter`.
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Fields, Type};
+
+/// Derives `IcebergRecord` and `IcebergRecordField` for a struct whose fields map to an Iceberg
+/// schema, so it can be fed through a `TypedWriter` (`iceberg::writer::base_writer::typed_writer::TypedWriter`)
+/// without hand-building Arrow arrays.
+///
+/// Every field must carry an `#[iceberg(id = N)]` attribute giving its Iceberg field id, which is
+/// attached to the generated Arrow field as `PARQUET_FIELD_ID_META_KEY` metadata. `Option<T>`
+/// fields are mapped to nullable Arrow fields; any field type that itself derives
+/// `IcebergRecordWriter` can be nested.
+#[proc_macro_derive(IcebergRecordWriter, attributes(iceberg))]
+pub fn derive_iceberg_record_writer(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+struct RecordField {
+    ident: syn::Ident,
+    id: u32,
+    optional: bool,
+    inner_ty: Type,
+}
+
+fn expand(input: DeriveInput) -> syn::Result<TokenStream2> {
+    let struct_name = &input.ident;
+    let Data::Struct(data) = &input.data else {
+        return Err(syn::Error::new_spanned(
+            &input,
+            "IcebergRecordWriter can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(syn::Error::new_spanned(
+            &input,
+            "IcebergRecordWriter requires named fields",
+        ));
+    };
+
+    let fields = fields
+        .named
+        .iter()
+        .map(parse_field)
+        .collect::<syn::Result<Vec<_>>>()?;
+
+    let arrow_fields = fields.iter().map(|field| {
+        let name = field.ident.to_string();
+        let id = field.id.to_string();
+        let inner_ty = &field.inner_ty;
+        let nullable = field.optional;
+        quote! {
+            ::std::sync::Arc::new(
+                ::iceberg::writer::__private::arrow_schema::Field::new(
+                    #name,
+                    <#inner_ty as ::iceberg::writer::base_writer::typed_writer::IcebergRecordField>::data_type(),
+                    #nullable,
+                )
+                .with_metadata(::std::collections::HashMap::from([(
+                    ::iceberg::writer::__private::parquet::arrow::PARQUET_FIELD_ID_META_KEY.to_owned(),
+                    #id.to_owned(),
+                )])),
+            )
+        }
+    });
+
+    let column_builders = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let inner_ty = &field.inner_ty;
+        let values = if field.optional {
+            quote! { records.iter().map(|record| record.#ident.clone()).collect::<::std::vec::Vec<_>>() }
+        } else {
+            quote! { records.iter().map(|record| ::std::option::Option::Some(record.#ident.clone())).collect::<::std::vec::Vec<_>>() }
+        };
+        quote! {
+            <#inner_ty as ::iceberg::writer::base_writer::typed_writer::IcebergRecordField>::to_array(#values)?
+        }
+    });
+
+    // Column builders for the nested-field case (`to_array` below), where each row's whole
+    // struct may itself be absent. These pull field values straight out of `values: Vec<Option<Self>>`
+    // instead of materializing a dummy `Self` for absent rows, so nesting an `IcebergRecordWriter`
+    // struct never requires it to implement `Default`.
+    let nested_column_builders = fields.iter().map(|field| {
+        let ident = &field.ident;
+        let inner_ty = &field.inner_ty;
+        let per_row = if field.optional {
+            quote! { record.as_ref().and_then(|record| record.#ident.clone()) }
+        } else {
+            quote! { record.as_ref().map(|record| record.#ident.clone()) }
+        };
+        quote! {
+            <#inner_ty as ::iceberg::writer::base_writer::typed_writer::IcebergRecordField>::to_array(
+                values.iter().map(|record| #per_row).collect::<::std::vec::Vec<_>>(),
+            )?
+        }
+    });
+
+    let field_count = fields.len();
+
+    Ok(quote! {
+        #[automatically_derived]
+        impl ::iceberg::writer::base_writer::typed_writer::IcebergRecord for #struct_name {
+            fn arrow_schema() -> ::std::sync::Arc<::iceberg::writer::__private::arrow_schema::Schema> {
+                ::std::sync::Arc::new(::iceberg::writer::__private::arrow_schema::Schema::new(vec![#(#arrow_fields),*]))
+            }
+
+            fn to_record_batch(
+                records: &[Self],
+            ) -> ::iceberg::Result<::iceberg::writer::__private::arrow_array::RecordBatch> {
+                let columns: ::std::vec::Vec<::iceberg::writer::__private::arrow_array::ArrayRef> =
+                    ::std::vec![#(#column_builders),*];
+                debug_assert_eq!(columns.len(), #field_count);
+                ::iceberg::writer::__private::arrow_array::RecordBatch::try_new(Self::arrow_schema(), columns).map_err(|e| {
+                    ::iceberg::Error::new(::iceberg::ErrorKind::DataInvalid, e.to_string())
+                })
+            }
+        }
+
+        #[automatically_derived]
+        impl ::iceberg::writer::base_writer::typed_writer::IcebergRecordField for #struct_name {
+            fn data_type() -> ::iceberg::writer::__private::arrow_schema::DataType {
+                ::iceberg::writer::__private::arrow_schema::DataType::Struct(
+                    <Self as ::iceberg::writer::base_writer::typed_writer::IcebergRecord>::arrow_schema()
+                        .fields()
+                        .clone(),
+                )
+            }
+
+            fn to_array(
+                values: ::std::vec::Vec<::std::option::Option<Self>>,
+            ) -> ::iceberg::Result<::iceberg::writer::__private::arrow_array::ArrayRef> {
+                let null_buffer = ::iceberg::writer::__private::arrow_buffer::NullBuffer::from_iter(
+                    values.iter().map(::std::option::Option::is_some),
+                );
+                let columns: ::std::vec::Vec<::iceberg::writer::__private::arrow_array::ArrayRef> =
+                    ::std::vec![#(#nested_column_builders),*];
+                debug_assert_eq!(columns.len(), #field_count);
+                ::std::result::Result::Ok(::std::sync::Arc::new(::iceberg::writer::__private::arrow_array::StructArray::new(
+                    <Self as ::iceberg::writer::base_writer::typed_writer::IcebergRecord>::arrow_schema()
+                        .fields()
+                        .clone(),
+                    columns,
+                    ::std::option::Option::Some(null_buffer),
+                )))
+            }
+        }
+    })
+}
+
+fn parse_field(field: &syn::Field) -> syn::Result<RecordField> {
+    let ident = field
+        .ident
+        .clone()
+        .ok_or_else(|| syn::Error::new_spanned(field, "tuple struct fields are not supported"))?;
+
+    let id = field
+        .attrs
+        .iter()
+        .find(|attr| attr.path().is_ident("iceberg"))
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                field,
+                format!("field `{ident}` is missing a `#[iceberg(id = ..)]` attribute"),
+            )
+        })
+        .and_then(parse_id_attr)?;
+
+    let (optional, inner_ty) = unwrap_option(&field.ty);
+
+    Ok(RecordField {
+        ident,
+        id,
+        optional,
+        inner_ty,
+    })
+}
+
+fn parse_id_attr(attr: &syn::Attribute) -> syn::Result<u32> {
+    let mut id = None;
+    attr.parse_nested_meta(|meta| {
+        if meta.path.is_ident("id") {
+            let value = meta.value()?;
+            let lit: syn::LitInt = value.parse()?;
+            id = Some(lit.base10_parse()?);
+            Ok(())
+        } else {
+            Err(meta.error("expected `id`"))
+        }
+    })?;
+    id.ok_or_else(|| syn::Error::new_spanned(attr, "expected `#[iceberg(id = ..)]`"))
+}
+
+/// If `ty` is `Option<T>`, return `(true, T)`; otherwise `(false, ty)`.
+fn unwrap_option(ty: &Type) -> (bool, Type) {
+    if let Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            if segment.ident == "Option" {
+                if let syn::PathArguments::AngleBracketed(args) = &segment.arguments {
+                    if let Some(syn::GenericArgument::Type(inner)) = args.args.first() {
+                        return (true, inner.clone());
+                    }
+                }
+            }
+        }
+    }
+    (false, ty.clone())
+}
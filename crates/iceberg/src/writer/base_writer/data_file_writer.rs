@@ -0,0 +1,117 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! This module provides the `DataFileWriter`, which wraps a [`FileWriter`] and produces
+//! [`DataFile`]s stamped with a fixed partition value.
+
+use arrow_array::RecordBatch;
+
+use crate::spec::{DataFile, Struct};
+use crate::writer::file_writer::{FileWriter, FileWriterBuilder};
+use crate::writer::{CurrentFileStatus, IcebergWriter, IcebergWriterBuilder};
+use crate::Result;
+
+/// Builder for [`DataFileWriter`].
+#[derive(Clone)]
+pub struct DataFileWriterBuilder<B: FileWriterBuilder> {
+    inner_builder: B,
+    partition_value: Struct,
+}
+
+impl<B: FileWriterBuilder> DataFileWriterBuilder<B> {
+    /// Create a new `DataFileWriterBuilder`. `partition_value` is the partition value that all
+    /// the data written by this writer belongs to, `None` for unpartitioned tables.
+    pub fn new(inner_builder: B, partition_value: Option<Struct>) -> Self {
+        Self {
+            inner_builder,
+            partition_value: partition_value.unwrap_or(Struct::empty()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<B: FileWriterBuilder> IcebergWriterBuilder for DataFileWriterBuilder<B> {
+    type R = DataFileWriter<B>;
+
+    async fn build(self) -> Result<Self::R> {
+        Ok(DataFileWriter {
+            inner_writer: Some(self.inner_builder.build().await?),
+            partition_value: self.partition_value,
+        })
+    }
+}
+
+/// A writer that writes [`RecordBatch`]es through the wrapped [`FileWriter`] and stamps every
+/// [`DataFile`] it produces with the writer's partition value.
+pub struct DataFileWriter<B: FileWriterBuilder> {
+    inner_writer: Option<B::R>,
+    partition_value: Struct,
+}
+
+#[async_trait::async_trait]
+impl<B: FileWriterBuilder> IcebergWriter for DataFileWriter<B> {
+    async fn write(&mut self, batch: RecordBatch) -> Result<()> {
+        self.inner_writer
+            .as_mut()
+            .expect("DataFileWriter has been closed")
+            .write(&batch)
+            .await
+    }
+
+    async fn close(&mut self) -> Result<Vec<DataFile>> {
+        let builders = self
+            .inner_writer
+            .take()
+            .expect("DataFileWriter has been closed")
+            .close()
+            .await?;
+
+        builders
+            .into_iter()
+            .map(|mut builder| {
+                builder.partition(self.partition_value.clone());
+                builder.build().map_err(Into::into)
+            })
+            .collect()
+    }
+}
+
+impl<B: FileWriterBuilder> CurrentFileStatus for DataFileWriter<B>
+where
+    B::R: CurrentFileStatus,
+{
+    fn current_file_path(&self) -> String {
+        self.inner_writer
+            .as_ref()
+            .expect("DataFileWriter has been closed")
+            .current_file_path()
+    }
+
+    fn current_row_num(&self) -> usize {
+        self.inner_writer
+            .as_ref()
+            .expect("DataFileWriter has been closed")
+            .current_row_num()
+    }
+
+    fn current_written_size(&self) -> usize {
+        self.inner_writer
+            .as_ref()
+            .expect("DataFileWriter has been closed")
+            .current_written_size()
+    }
+}
@@ -0,0 +1,507 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! This module provides the `FanoutPartitionWriter`, which demultiplexes a single stream of
+//! record batches into one data file writer per distinct partition value.
+
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+use arrow_array::{Array, ArrayRef, RecordBatch};
+use arrow_ord::partition::partition;
+use arrow_ord::sort::{lexsort_to_indices, SortColumn};
+use arrow_select::take::take;
+
+use crate::spec::{DataFile, Literal, PartitionSpec, Schema, Struct};
+use crate::transform::create_transform_function;
+use crate::writer::base_writer::data_file_writer::{DataFileWriter, DataFileWriterBuilder};
+use crate::writer::file_writer::FileWriterBuilder;
+use crate::writer::{IcebergWriter, IcebergWriterBuilder};
+use crate::{Error, ErrorKind, Result};
+
+/// Builder for [`FanoutPartitionWriter`].
+#[derive(Clone)]
+pub struct FanoutPartitionWriterBuilder<B: FileWriterBuilder> {
+    inner_builder: B,
+    partition_spec: Arc<PartitionSpec>,
+    schema: Arc<Schema>,
+    /// Maximum number of partition writers allowed to be open at the same time. Once exceeded,
+    /// the least-recently-used writer is closed and its data files are spilled out.
+    max_concurrent_writers: usize,
+}
+
+impl<B: FileWriterBuilder> FanoutPartitionWriterBuilder<B> {
+    /// Create a new `FanoutPartitionWriterBuilder`.
+    pub fn new(inner_builder: B, partition_spec: Arc<PartitionSpec>, schema: Arc<Schema>) -> Self {
+        Self {
+            inner_builder,
+            partition_spec,
+            schema,
+            max_concurrent_writers: 100,
+        }
+    }
+
+    /// Set the maximum number of partition writers allowed to be open concurrently. When a
+    /// batch targets a new partition and the cap has already been reached, the
+    /// least-recently-used writer is closed to make room.
+    pub fn with_max_concurrent_writers(mut self, max_concurrent_writers: usize) -> Self {
+        self.max_concurrent_writers = max_concurrent_writers;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl<B: FileWriterBuilder> IcebergWriterBuilder for FanoutPartitionWriterBuilder<B> {
+    type R = FanoutPartitionWriter<B>;
+
+    async fn build(self) -> Result<Self::R> {
+        if self.max_concurrent_writers == 0 {
+            return Err(Error::new(
+                ErrorKind::DataInvalid,
+                "max_concurrent_writers must be greater than zero",
+            ));
+        }
+
+        Ok(FanoutPartitionWriter {
+            inner_builder: self.inner_builder,
+            partition_spec: self.partition_spec,
+            schema: self.schema,
+            max_concurrent_writers: self.max_concurrent_writers,
+            writers: HashMap::new(),
+            lru: VecDeque::new(),
+            closed_data_files: Vec::new(),
+        })
+    }
+}
+
+/// An [`IcebergWriter`] that splits every incoming [`RecordBatch`] by partition value and routes
+/// each slice to a dedicated data file writer, opening a new one the first time a partition
+/// value is seen and closing the least-recently-used one once `max_concurrent_writers` is
+/// exceeded.
+pub struct FanoutPartitionWriter<B: FileWriterBuilder> {
+    inner_builder: B,
+    partition_spec: Arc<PartitionSpec>,
+    schema: Arc<Schema>,
+    max_concurrent_writers: usize,
+    writers: HashMap<Struct, DataFileWriter<B>>,
+    lru: VecDeque<Struct>,
+    closed_data_files: Vec<DataFile>,
+}
+
+impl<B: FileWriterBuilder> FanoutPartitionWriter<B> {
+    /// Compute the partition `Struct` for every row of `batch`, grouped into contiguous row
+    /// ranges so each range can be sliced out of the (partition-sorted) batch directly.
+    fn split_by_partition(&self, batch: &RecordBatch) -> Result<Vec<(Struct, RecordBatch)>> {
+        let partition_type = self.partition_spec.partition_type(&self.schema)?;
+        let partition_columns = self
+            .partition_spec
+            .fields()
+            .iter()
+            .map(|field| {
+                let source_field = self.schema.field_by_id(field.source_id).ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::DataInvalid,
+                        format!(
+                            "Partition source field {} not found in schema",
+                            field.source_id
+                        ),
+                    )
+                })?;
+                let source_array = batch.column_by_name(&source_field.name).ok_or_else(|| {
+                    Error::new(
+                        ErrorKind::DataInvalid,
+                        format!("Column {} not found in record batch", source_field.name),
+                    )
+                })?;
+                create_transform_function(&field.transform)?.transform(source_array.clone())
+            })
+            .collect::<Result<Vec<ArrayRef>>>()?;
+
+        if partition_columns.is_empty() {
+            return Err(Error::new(
+                ErrorKind::DataInvalid,
+                "Cannot fan out by partition: partition spec has no fields",
+            ));
+        }
+
+        // Sort the batch by partition column so rows belonging to the same partition become
+        // contiguous, then use the `partition` kernel to recover the resulting row ranges.
+        let sort_columns: Vec<SortColumn> = partition_columns
+            .iter()
+            .map(|array| SortColumn {
+                values: array.clone(),
+                options: None,
+            })
+            .collect();
+        let sort_indices = lexsort_to_indices(&sort_columns, None)
+            .map_err(|e| Error::new(ErrorKind::DataInvalid, e.to_string()))?;
+
+        let sorted_columns = batch
+            .columns()
+            .iter()
+            .chain(partition_columns.iter())
+            .map(|column| take(column, &sort_indices, None))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| Error::new(ErrorKind::DataInvalid, e.to_string()))?;
+        let (sorted_batch_columns, sorted_partition_columns) =
+            sorted_columns.split_at(batch.num_columns());
+        let sorted_batch = RecordBatch::try_new(batch.schema(), sorted_batch_columns.to_vec())
+            .map_err(|e| Error::new(ErrorKind::DataInvalid, e.to_string()))?;
+
+        let ranges = partition(sorted_partition_columns)
+            .map_err(|e| Error::new(ErrorKind::DataInvalid, e.to_string()))?
+            .ranges();
+
+        ranges
+            .into_iter()
+            .map(|range| {
+                let partition_value = Struct::from_iter(
+                    sorted_partition_columns
+                        .iter()
+                        .zip(partition_type.fields())
+                        .map(|(array, field)| {
+                            if array.is_null(range.start) {
+                                return Ok(None);
+                            }
+                            Literal::try_from_array(array.as_ref(), range.start, &field.field_type)
+                                .map(Some)
+                        })
+                        .collect::<Result<Vec<_>>>()?,
+                );
+                let slice = sorted_batch.slice(range.start, range.end - range.start);
+                Ok((partition_value, slice))
+            })
+            .collect()
+    }
+
+    /// If `partition_value` is already open, mark it as the most recently used writer and
+    /// report no spill. Otherwise, if the cap has already been reached, evict the
+    /// least-recently-used writer to make room and return its finished data files.
+    ///
+    /// This does *not* register `partition_value` itself in `lru` - the caller must only do
+    /// that once it has actually built and inserted a writer for it, so a failed
+    /// `DataFileWriterBuilder::build()` never leaves an entry in `lru` with no corresponding
+    /// writer (which would silently shrink the effective cap by one until that entry aged out).
+    ///
+    /// The `lru` scan below is `O(max_concurrent_writers)` per touched partition; fine at the
+    /// default cap of 100, but worth revisiting with a proper LRU structure if callers start
+    /// raising it much higher.
+    async fn touch(&mut self, partition_value: &Struct) -> Result<Vec<DataFile>> {
+        if let Some(pos) = self.lru.iter().position(|v| v == partition_value) {
+            self.lru.remove(pos);
+            self.lru.push_back(partition_value.clone());
+            return Ok(Vec::new());
+        }
+
+        let mut spilled = Vec::new();
+        if self.lru.len() >= self.max_concurrent_writers {
+            if let Some(evicted) = self.lru.pop_front() {
+                if let Some(mut writer) = self.writers.remove(&evicted) {
+                    spilled = writer.close().await?;
+                }
+            }
+        }
+        Ok(spilled)
+    }
+}
+
+#[async_trait::async_trait]
+impl<B: FileWriterBuilder> IcebergWriter for FanoutPartitionWriter<B> {
+    async fn write(&mut self, batch: RecordBatch) -> Result<()> {
+        for (partition_value, slice) in self.split_by_partition(&batch)? {
+            let spilled = self.touch(&partition_value).await?;
+            self.closed_data_files.extend(spilled);
+
+            if !self.writers.contains_key(&partition_value) {
+                let writer = DataFileWriterBuilder::new(
+                    self.inner_builder.clone(),
+                    Some(partition_value.clone()),
+                )
+                .build()
+                .await?;
+                self.writers.insert(partition_value.clone(), writer);
+                // Only now that the writer exists does this partition occupy a slot against
+                // `max_concurrent_writers` - registering it any earlier would leave `lru` out
+                // of sync with `writers` if `build()` above had failed.
+                self.lru.push_back(partition_value.clone());
+            }
+
+            self.writers
+                .get_mut(&partition_value)
+                .expect("writer was just inserted or is already open")
+                .write(slice)
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn close(&mut self) -> Result<Vec<DataFile>> {
+        let mut data_files = std::mem::take(&mut self.closed_data_files);
+        for (_, mut writer) in self.writers.drain() {
+            data_files.extend(writer.close().await?);
+        }
+        self.lru.clear();
+        Ok(data_files)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    use arrow_array::Int32Array;
+
+    use super::*;
+    use crate::spec::{NestedField, PrimitiveType, Transform, Type};
+    use crate::writer::file_writer::FileWriter;
+    use crate::{Error, ErrorKind};
+
+    /// A [`FileWriterBuilder`] that stamps every data file it produces with a unique path, so
+    /// tests can tell which partition's writer produced which file.
+    #[derive(Clone)]
+    struct MockWriterBuilder {
+        next_id: Arc<AtomicUsize>,
+        fail_next_build: Arc<AtomicBool>,
+    }
+
+    impl MockWriterBuilder {
+        fn new() -> Self {
+            Self {
+                next_id: Arc::new(AtomicUsize::new(0)),
+                fail_next_build: Arc::new(AtomicBool::new(false)),
+            }
+        }
+    }
+
+    struct MockWriter {
+        id: usize,
+        row_num: usize,
+    }
+
+    #[async_trait::async_trait]
+    impl FileWriterBuilder for MockWriterBuilder {
+        type R = MockWriter;
+
+        async fn build(self) -> Result<Self::R> {
+            if self.fail_next_build.swap(false, Ordering::SeqCst) {
+                return Err(Error::new(ErrorKind::Unexpected, "injected build failure"));
+            }
+            Ok(MockWriter {
+                id: self.next_id.fetch_add(1, Ordering::SeqCst),
+                row_num: 0,
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FileWriter for MockWriter {
+        async fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+            self.row_num += batch.num_rows();
+            Ok(())
+        }
+
+        async fn close(self) -> Result<Vec<crate::spec::DataFileBuilder>> {
+            Ok(vec![crate::spec::DataFileBuilder::new()
+                .file_path(format!("file-{}", self.id))
+                .file_format(crate::spec::DataFileFormat::Parquet)
+                .record_count(self.row_num as u64)
+                .file_size_in_bytes(0)
+                .clone()])
+        }
+    }
+
+    fn int_schema() -> Arc<Schema> {
+        Arc::new(
+            Schema::builder()
+                .with_schema_id(1)
+                .with_fields(vec![Arc::new(NestedField::optional(
+                    1,
+                    "id",
+                    Type::Primitive(PrimitiveType::Int),
+                ))])
+                .build()
+                .unwrap(),
+        )
+    }
+
+    fn identity_partition_spec(schema: &Schema) -> Arc<PartitionSpec> {
+        Arc::new(
+            PartitionSpec::builder(schema)
+                .with_spec_id(0)
+                .add_partition_field("id", "id", Transform::Identity)
+                .unwrap()
+                .build()
+                .unwrap(),
+        )
+    }
+
+    fn batch_of(ids: Vec<Option<i32>>) -> RecordBatch {
+        let arrow_schema = Arc::new(arrow_schema::Schema::new(vec![arrow_schema::Field::new(
+            "id",
+            arrow_schema::DataType::Int32,
+            true,
+        )]));
+        RecordBatch::try_new(arrow_schema, vec![Arc::new(Int32Array::from(ids))]).unwrap()
+    }
+
+    #[tokio::test]
+    async fn split_by_partition_groups_rows_and_preserves_nulls() {
+        let schema = int_schema();
+        let partition_spec = identity_partition_spec(&schema);
+        let writer = FanoutPartitionWriterBuilder::new(
+            MockWriterBuilder::new(),
+            partition_spec,
+            schema,
+        )
+        .build()
+        .await
+        .unwrap();
+
+        let batch = batch_of(vec![Some(1), None, Some(1), Some(2)]);
+        let groups = writer.split_by_partition(&batch).unwrap();
+
+        assert_eq!(groups.len(), 3);
+        let null_group = groups
+            .iter()
+            .find(|(value, _)| value.fields().first().unwrap().is_none())
+            .expect("a null partition group must be present, not dropped or errored out");
+        assert_eq!(null_group.1.num_rows(), 1);
+    }
+
+    #[tokio::test]
+    async fn rejects_an_unpartitioned_spec_instead_of_relying_on_the_sort_kernel() {
+        let schema = int_schema();
+        let partition_spec = Arc::new(
+            PartitionSpec::builder(&schema)
+                .with_spec_id(0)
+                .build()
+                .unwrap(),
+        );
+        let writer = FanoutPartitionWriterBuilder::new(
+            MockWriterBuilder::new(),
+            partition_spec,
+            schema,
+        )
+        .build()
+        .await
+        .unwrap();
+
+        let batch = batch_of(vec![Some(1)]);
+        assert!(writer.split_by_partition(&batch).is_err());
+    }
+
+    #[tokio::test]
+    async fn accumulates_multiple_writes_to_the_same_partition_into_one_data_file() {
+        let schema = int_schema();
+        let partition_spec = identity_partition_spec(&schema);
+        let mut writer = FanoutPartitionWriterBuilder::new(
+            MockWriterBuilder::new(),
+            partition_spec,
+            schema,
+        )
+        .build()
+        .await
+        .unwrap();
+
+        // Two separate batches for the same partition, under the default cap of 100, must
+        // be routed to the same underlying writer rather than opening a new one each time.
+        writer.write(batch_of(vec![Some(1)])).await.unwrap();
+        writer.write(batch_of(vec![Some(1), Some(1)])).await.unwrap();
+
+        let data_files = writer.close().await.unwrap();
+        assert_eq!(data_files.len(), 1);
+        assert_eq!(data_files[0].record_count(), 3);
+        assert_eq!(
+            data_files[0].partition(),
+            &Struct::from_iter([Some(Literal::int(1))])
+        );
+    }
+
+    #[tokio::test]
+    async fn evicts_the_least_recently_used_writer_once_the_cap_is_reached() {
+        let schema = int_schema();
+        let partition_spec = identity_partition_spec(&schema);
+        let mut writer = FanoutPartitionWriterBuilder::new(
+            MockWriterBuilder::new(),
+            partition_spec,
+            schema,
+        )
+        .with_max_concurrent_writers(1)
+        .build()
+        .await
+        .unwrap();
+
+        // Partition 1 opens a writer, then partition 2 forces it to spill since only one
+        // concurrent writer is allowed.
+        writer.write(batch_of(vec![Some(1)])).await.unwrap();
+        writer.write(batch_of(vec![Some(2)])).await.unwrap();
+
+        let mut data_files = writer.close().await.unwrap();
+        assert_eq!(data_files.len(), 2);
+
+        // Each data file must be stamped with the partition value of the writer that produced
+        // it, not e.g. whichever partition happened to close last.
+        data_files.sort_by_key(|data_file| data_file.file_path().to_string());
+        assert_eq!(
+            data_files[0].partition(),
+            &Struct::from_iter([Some(Literal::int(1))])
+        );
+        assert_eq!(
+            data_files[1].partition(),
+            &Struct::from_iter([Some(Literal::int(2))])
+        );
+    }
+
+    #[tokio::test]
+    async fn a_failed_build_does_not_leave_an_orphaned_lru_entry() {
+        let schema = int_schema();
+        let partition_spec = identity_partition_spec(&schema);
+        let inner_builder = MockWriterBuilder::new();
+        let fail_next_build = inner_builder.fail_next_build.clone();
+        let mut writer = FanoutPartitionWriterBuilder::new(inner_builder, partition_spec, schema)
+            .with_max_concurrent_writers(2)
+            .build()
+            .await
+            .unwrap();
+
+        writer.write(batch_of(vec![Some(1)])).await.unwrap();
+
+        // Partition 2's writer fails to build. If `touch()` had already reserved a slot for it
+        // in `lru`, that slot would sit there uncounted against any real writer, depressing the
+        // effective cap by one until it aged out.
+        fail_next_build.store(true, Ordering::SeqCst);
+        assert!(writer.write(batch_of(vec![Some(2)])).await.is_err());
+
+        // With the cap still genuinely at 2 open writers (only partition 1's), opening a second
+        // distinct partition must not evict partition 1's writer.
+        writer.write(batch_of(vec![Some(4)])).await.unwrap();
+        writer.write(batch_of(vec![Some(1)])).await.unwrap();
+
+        let mut data_files = writer.close().await.unwrap();
+        assert_eq!(data_files.len(), 2);
+
+        // If the failed build had left a phantom `lru` entry, partition 1's writer would have
+        // been evicted prematurely between the two `write`s above, splitting its rows across
+        // two separate data files instead of accumulating into one.
+        data_files.sort_by_key(|data_file| data_file.file_path().to_string());
+        let partition_1_file = data_files
+            .iter()
+            .find(|data_file| data_file.partition() == &Struct::from_iter([Some(Literal::int(1))]))
+            .expect("partition 1 must still produce a data file");
+        assert_eq!(partition_1_file.record_count(), 2);
+    }
+}
@@ -0,0 +1,294 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! This module provides `TypedWriter`, an adapter that lets a `RecordBatch`-based
+//! [`IcebergWriter`] accept rows of an arbitrary Rust type, typically one annotated with
+//! `#[derive(IcebergRecordWriter)]` (see the `iceberg-derive` crate).
+
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+use arrow_array::{
+    ArrayRef, BooleanArray, Float32Array, Float64Array, Int32Array, Int64Array, RecordBatch,
+    StringArray,
+};
+use arrow_schema::{DataType, Schema};
+
+use crate::spec::DataFile;
+use crate::writer::{IcebergWriter, IcebergWriterBuilder};
+use crate::Result;
+
+/// Implemented by types annotated with `#[derive(IcebergRecordWriter)]`: knows how to turn a
+/// slice of records into the [`RecordBatch`] representation the rest of the writer stack expects.
+pub trait IcebergRecord: Sized + Send + 'static {
+    /// The Arrow schema that [`Self::to_record_batch`] produces, with
+    /// `PARQUET_FIELD_ID_META_KEY` metadata attached to every field in field-declaration order.
+    fn arrow_schema() -> Arc<Schema>;
+    /// Build a `RecordBatch` out of a slice of records, in field-declaration order.
+    fn to_record_batch(records: &[Self]) -> Result<RecordBatch>;
+}
+
+/// Implemented for every type that can appear as a field of an [`IcebergRecord`] struct: a
+/// primitive type or another `IcebergRecord` struct for nesting. It is not implemented for
+/// `Option<T>`; an `Option<T>` field uses `T`'s implementation, with `None` as a null slot.
+pub trait IcebergRecordField: Sized {
+    /// The Arrow data type used to represent this field.
+    fn data_type() -> DataType;
+    /// Build the Arrow array for a column of values, `None` mapping to a null slot.
+    fn to_array(values: Vec<Option<Self>>) -> Result<ArrayRef>;
+}
+
+macro_rules! impl_iceberg_record_field_for_primitive {
+    ($ty:ty, $array:ty, $data_type:expr) => {
+        impl IcebergRecordField for $ty {
+            fn data_type() -> DataType {
+                $data_type
+            }
+
+            fn to_array(values: Vec<Option<Self>>) -> Result<ArrayRef> {
+                Ok(Arc::new(<$array>::from(values)))
+            }
+        }
+    };
+}
+
+impl_iceberg_record_field_for_primitive!(bool, BooleanArray, DataType::Boolean);
+impl_iceberg_record_field_for_primitive!(i32, Int32Array, DataType::Int32);
+impl_iceberg_record_field_for_primitive!(i64, Int64Array, DataType::Int64);
+impl_iceberg_record_field_for_primitive!(f32, Float32Array, DataType::Float32);
+impl_iceberg_record_field_for_primitive!(f64, Float64Array, DataType::Float64);
+impl_iceberg_record_field_for_primitive!(String, StringArray, DataType::Utf8);
+
+/// Builder for [`TypedWriter`].
+#[derive(Clone)]
+pub struct TypedWriterBuilder<T: IcebergRecord, B> {
+    inner_builder: B,
+    _marker: PhantomData<T>,
+}
+
+impl<T: IcebergRecord, B> TypedWriterBuilder<T, B> {
+    /// Create a new `TypedWriterBuilder` wrapping a `RecordBatch`-based iceberg writer builder.
+    pub fn new(inner_builder: B) -> Self {
+        Self {
+            inner_builder,
+            _marker: PhantomData,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<T, B> IcebergWriterBuilder<Vec<T>, Vec<DataFile>> for TypedWriterBuilder<T, B>
+where
+    T: IcebergRecord,
+    B: IcebergWriterBuilder,
+{
+    type R = TypedWriter<T, B::R>;
+
+    async fn build(self) -> Result<Self::R> {
+        Ok(TypedWriter {
+            inner: self.inner_builder.build().await?,
+            _marker: PhantomData,
+        })
+    }
+}
+
+/// An [`IcebergWriter<Vec<T>>`] adapter that converts each batch of `T` records into a
+/// `RecordBatch` via [`IcebergRecord::to_record_batch`] and forwards it to an underlying
+/// `RecordBatch`-based writer, so callers can write their own structs without hand-building
+/// Arrow arrays.
+pub struct TypedWriter<T: IcebergRecord, W> {
+    inner: W,
+    _marker: PhantomData<T>,
+}
+
+#[async_trait::async_trait]
+impl<T, W> IcebergWriter<Vec<T>, Vec<DataFile>> for TypedWriter<T, W>
+where
+    T: IcebergRecord,
+    W: IcebergWriter,
+{
+    async fn write(&mut self, records: Vec<T>) -> Result<()> {
+        let batch = T::to_record_batch(&records)?;
+        self.inner.write(batch).await
+    }
+
+    async fn close(&mut self) -> Result<Vec<DataFile>> {
+        self.inner.close().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_array::{
+        Array, BooleanArray, Float32Array, Float64Array, Int32Array, Int64Array, StringArray,
+        StructArray,
+    };
+    use parquet::arrow::PARQUET_FIELD_ID_META_KEY;
+
+    use super::*;
+    use crate::writer::IcebergRecordWriter;
+
+    #[derive(IcebergRecordWriter, Clone)]
+    struct Address {
+        #[iceberg(id = 10)]
+        city: String,
+    }
+
+    #[derive(IcebergRecordWriter, Clone)]
+    struct Person {
+        #[iceberg(id = 1)]
+        id: i64,
+        #[iceberg(id = 2)]
+        nickname: Option<String>,
+        #[iceberg(id = 3)]
+        address: Option<Address>,
+    }
+
+    #[derive(IcebergRecordWriter, Clone)]
+    struct AllPrimitives {
+        #[iceberg(id = 1)]
+        a: bool,
+        #[iceberg(id = 2)]
+        b: i32,
+        #[iceberg(id = 3)]
+        c: i64,
+        #[iceberg(id = 4)]
+        d: f32,
+        #[iceberg(id = 5)]
+        e: f64,
+    }
+
+    #[test]
+    fn to_record_batch_handles_optional_and_nested_fields() {
+        let records = vec![
+            Person {
+                id: 1,
+                nickname: Some("ann".to_owned()),
+                address: Some(Address {
+                    city: "nyc".to_owned(),
+                }),
+            },
+            Person {
+                id: 2,
+                nickname: None,
+                address: None,
+            },
+        ];
+
+        let batch = Person::to_record_batch(&records).unwrap();
+        assert_eq!(batch.num_rows(), 2);
+
+        let id = batch
+            .column_by_name("id")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(id.value(0), 1);
+        assert_eq!(id.value(1), 2);
+
+        let nickname = batch
+            .column_by_name("nickname")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StringArray>()
+            .unwrap();
+        assert!(!nickname.is_null(0));
+        assert!(nickname.is_null(1));
+
+        let address = batch
+            .column_by_name("address")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<StructArray>()
+            .unwrap();
+        assert!(!address.is_null(0));
+        assert!(address.is_null(1));
+    }
+
+    #[test]
+    fn to_record_batch_covers_every_primitive_field_type() {
+        let records = vec![AllPrimitives {
+            a: true,
+            b: 7,
+            c: 42,
+            d: 1.5,
+            e: 2.5,
+        }];
+
+        let batch = AllPrimitives::to_record_batch(&records).unwrap();
+
+        let a = batch
+            .column_by_name("a")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<BooleanArray>()
+            .unwrap();
+        assert!(a.value(0));
+
+        let b = batch
+            .column_by_name("b")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Int32Array>()
+            .unwrap();
+        assert_eq!(b.value(0), 7);
+
+        let c = batch
+            .column_by_name("c")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .unwrap();
+        assert_eq!(c.value(0), 42);
+
+        let d = batch
+            .column_by_name("d")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Float32Array>()
+            .unwrap();
+        assert_eq!(d.value(0), 1.5);
+
+        let e = batch
+            .column_by_name("e")
+            .unwrap()
+            .as_any()
+            .downcast_ref::<Float64Array>()
+            .unwrap();
+        assert_eq!(e.value(0), 2.5);
+    }
+
+    #[test]
+    fn arrow_schema_attaches_parquet_field_id_metadata() {
+        let schema = Person::arrow_schema();
+        for (name, id) in [("id", "1"), ("nickname", "2"), ("address", "3")] {
+            let field = schema.field_with_name(name).unwrap();
+            assert_eq!(
+                field.metadata().get(PARQUET_FIELD_ID_META_KEY),
+                Some(&id.to_owned())
+            );
+        }
+
+        let address_schema = Address::arrow_schema();
+        let city_field = address_schema.field_with_name("city").unwrap();
+        assert_eq!(
+            city_field.metadata().get(PARQUET_FIELD_ID_META_KEY),
+            Some(&"10".to_owned())
+        );
+    }
+}
@@ -0,0 +1,106 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! An [`AsyncWrite`] adapter that counts every byte actually forwarded to the wrapped inner
+//! writer, so the real number of bytes written survives the inner writer being consumed (e.g. by
+//! a `close()` that takes `self` by value).
+
+use std::io;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use tokio::io::AsyncWrite;
+
+use crate::io::FileWrite;
+
+/// Wraps an inner [`FileWrite`], atomically accumulating every byte count returned by a
+/// successful `poll_write` into a shared counter that the caller can keep reading after the
+/// writer itself has been dropped or consumed.
+pub(crate) struct ByteCountingWriter<W> {
+    inner: W,
+    written: Arc<AtomicUsize>,
+}
+
+impl<W> ByteCountingWriter<W> {
+    pub(crate) fn new(inner: W, written: Arc<AtomicUsize>) -> Self {
+        Self { inner, written }
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for ByteCountingWriter<W> {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        match Pin::new(&mut self.inner).poll_write(cx, buf) {
+            Poll::Ready(Ok(written)) => {
+                self.written.fetch_add(written, Ordering::Relaxed);
+                Poll::Ready(Ok(written))
+            }
+            other => other,
+        }
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+impl<W: FileWrite> FileWrite for ByteCountingWriter<W> {}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::Pin;
+    use std::task::{Context, Poll, Wake, Waker};
+
+    use super::*;
+
+    struct NoopWake;
+    impl Wake for NoopWake {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    #[test]
+    fn counter_survives_the_inner_writer_being_consumed() {
+        let waker = Waker::from(Arc::new(NoopWake));
+        let mut cx = Context::from_waker(&waker);
+
+        let written = Arc::new(AtomicUsize::new(0));
+        let mut writer = ByteCountingWriter::new(Vec::<u8>::new(), written.clone());
+
+        assert!(matches!(
+            Pin::new(&mut writer).poll_write(&mut cx, b"abc"),
+            Poll::Ready(Ok(3))
+        ));
+        assert!(matches!(
+            Pin::new(&mut writer).poll_write(&mut cx, b"defg"),
+            Poll::Ready(Ok(4))
+        ));
+
+        // Dropping `writer` (as `AsyncArrowWriter::close` does with its inner sink) must not
+        // reset the count: the whole point of the shared counter is that it outlives the writer.
+        drop(writer);
+        assert_eq!(written.load(Ordering::Relaxed), 7);
+    }
+}
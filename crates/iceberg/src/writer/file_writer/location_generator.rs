@@ -0,0 +1,143 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Location generator used to generate the location of a new file for a writer.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use uuid::Uuid;
+
+use crate::spec::{DataFileFormat, TableMetadata};
+use crate::Result;
+
+/// `LocationGenerator` used to generate the location of data file.
+pub trait LocationGenerator: Clone + Send + 'static {
+    /// Generate an absolute path for the given file name.
+    fn generate_location(&self, file_name: &str) -> String;
+}
+
+/// `DefaultLocationGenerator` used to generate the data dir location of data file.
+/// The location is generated by concatenating the table location and the `data` directory,
+/// following the spec in <https://iceberg.apache.org/spec/#file-system-tables>.
+#[derive(Clone, Debug)]
+pub struct DefaultLocationGenerator {
+    dir_path: String,
+}
+
+impl DefaultLocationGenerator {
+    /// Create a new `DefaultLocationGenerator`.
+    pub fn new(table_metadata: TableMetadata) -> Result<Self> {
+        let dir_path = format!("{}/data", table_metadata.location());
+        Ok(Self { dir_path })
+    }
+}
+
+impl LocationGenerator for DefaultLocationGenerator {
+    fn generate_location(&self, file_name: &str) -> String {
+        format!("{}/{}", self.dir_path, file_name)
+    }
+}
+
+/// `FileNameGenerator` used to generate file name for a writer.
+pub trait FileNameGenerator: Clone + Send + 'static {
+    /// Generate a file name.
+    fn generate_file_name(&self) -> String;
+}
+
+/// `DefaultFileNameGenerator` used to generate file name for a writer. The file name format
+/// is `[prefix]-[uuid][optional "-suffix"]-[file_count].[format]`.
+#[derive(Clone, Debug)]
+pub struct DefaultFileNameGenerator {
+    prefix: String,
+    suffix: String,
+    format: DataFileFormat,
+    file_count: Arc<AtomicU64>,
+}
+
+impl DefaultFileNameGenerator {
+    /// Create a new `DefaultFileNameGenerator`.
+    pub fn new(prefix: String, suffix: Option<String>, format: DataFileFormat) -> Self {
+        let suffix = if let Some(suffix) = suffix {
+            format!("-{suffix}")
+        } else {
+            String::new()
+        };
+
+        Self {
+            prefix,
+            suffix,
+            format,
+            file_count: Arc::new(AtomicU64::new(0)),
+        }
+    }
+}
+
+impl FileNameGenerator for DefaultFileNameGenerator {
+    fn generate_file_name(&self) -> String {
+        let file_count = self.file_count.fetch_add(1, Ordering::Relaxed);
+        format!(
+            "{}-{}{}-{file_count}.{}",
+            self.prefix,
+            Uuid::now_v7(),
+            self.suffix,
+            self.format
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pins `generate_file_name`'s output to the format documented on
+    /// [`DefaultFileNameGenerator`], so a doc-comment edit that drifts from the real format gets
+    /// caught here instead of relying on inspection.
+    #[test]
+    fn generate_file_name_matches_the_documented_format() {
+        let generator = DefaultFileNameGenerator::new(
+            "prefix".to_string(),
+            Some("suffix".to_string()),
+            DataFileFormat::Parquet,
+        );
+
+        let first = generator.generate_file_name();
+        let second = generator.generate_file_name();
+
+        let parse = |name: &str| {
+            let name = name.strip_prefix("prefix-").unwrap();
+            let name = name.strip_suffix(".parquet").unwrap();
+            let (uuid, rest) = name.split_once("-suffix-").unwrap();
+            Uuid::parse_str(uuid).unwrap();
+            rest.parse::<u64>().unwrap()
+        };
+        assert_eq!(parse(&first), 0);
+        assert_eq!(parse(&second), 1);
+    }
+
+    #[test]
+    fn generate_file_name_omits_the_suffix_segment_when_none_is_given() {
+        let generator =
+            DefaultFileNameGenerator::new("prefix".to_string(), None, DataFileFormat::Parquet);
+
+        let name = generator.generate_file_name();
+        let name = name.strip_prefix("prefix-").unwrap();
+        let (uuid, rest) = name.strip_suffix(".parquet").unwrap().rsplit_once('-').unwrap();
+        Uuid::parse_str(uuid).unwrap();
+        assert_eq!(rest, "0");
+    }
+}
@@ -0,0 +1,53 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! This module contains the file writer trait and its implementation.
+
+mod byte_counting_writer;
+pub mod location_generator;
+mod multipart_upload_writer;
+pub mod parquet_writer;
+pub mod rolling_writer;
+
+pub use parquet_writer::ParquetWriterBuilder;
+
+use arrow_array::RecordBatch;
+
+use crate::spec::DataFileBuilder;
+use crate::Result;
+
+/// The builder for file writer.
+#[async_trait::async_trait]
+pub trait FileWriterBuilder: Send + Clone + 'static {
+    /// The associated file writer type.
+    type R: FileWriter;
+    /// Build a file writer.
+    async fn build(self) -> Result<Self::R>;
+}
+
+/// The file writer write record batches into a physical file.
+///
+/// Different from [`crate::writer::IcebergWriter`], it only focuses on the physical file format
+/// and doesn't know the logical iceberg format, like partition value or equality ids.
+#[async_trait::async_trait]
+pub trait FileWriter: Send + 'static {
+    /// Write record batch to file.
+    async fn write(&mut self, batch: &RecordBatch) -> Result<()>;
+    /// Close the writer and return the written data file builders. The caller is responsible for
+    /// completing them with the remaining iceberg-level metadata (e.g. partition value).
+    async fn close(self) -> Result<Vec<DataFileBuilder>>;
+}
@@ -0,0 +1,273 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! An [`AsyncWrite`] adapter that buffers up to a configurable size before handing data to the
+//! underlying object-store multipart upload, so encoders that write in small increments (like
+//! the parquet writer) don't drive one tiny part per write.
+
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use tokio::io::AsyncWrite;
+
+use crate::io::FileWrite;
+
+/// Wraps an inner [`FileWrite`] (an object-store multipart upload in the streaming case) behind
+/// a bounded buffer: writes accumulate locally and are only forwarded - as a single part upload -
+/// once `buffer_size` is exceeded, instead of on every call.
+///
+/// We never call `poll_shutdown` on the inner writer except as the very last step of a
+/// successful close: object-store multipart writers complete the upload on shutdown and abort it
+/// on drop otherwise, so as long as we only shut down after every buffered byte has been
+/// forwarded, a `close` that bails out midway (and simply drops this writer) aborts the upload
+/// instead of leaking parts.
+pub(crate) struct MultipartUploadWriter {
+    inner: Box<dyn FileWrite>,
+    buffer: Vec<u8>,
+    buffer_size: usize,
+}
+
+impl MultipartUploadWriter {
+    pub(crate) fn new(inner: Box<dyn FileWrite>, buffer_size: usize) -> Self {
+        Self {
+            inner,
+            buffer: Vec::with_capacity(buffer_size),
+            buffer_size,
+        }
+    }
+
+    fn poll_flush_buffer(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        while !self.buffer.is_empty() {
+            let written = match Pin::new(&mut self.inner).poll_write(cx, &self.buffer) {
+                Poll::Ready(Ok(written)) => written,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            };
+            self.buffer.drain(..written);
+        }
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl AsyncWrite for MultipartUploadWriter {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        // Flush any previously buffered data *before* absorbing `buf`. `AsyncWrite` requires that
+        // a `Pending` result be retried with the same `buf`, so `buf` must not be appended to
+        // `self.buffer` until we know the flush won't need another retry - otherwise a `Pending`
+        // retry would append the same bytes twice.
+        if self.buffer.len() >= self.buffer_size {
+            match self.as_mut().poll_flush_buffer(cx) {
+                Poll::Ready(Ok(())) => {}
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+        self.buffer.extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.as_mut().poll_flush_buffer(cx) {
+            Poll::Ready(Ok(())) => Pin::new(&mut self.inner).poll_flush(cx),
+            other => other,
+        }
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        match self.as_mut().poll_flush_buffer(cx) {
+            Poll::Ready(Ok(())) => {}
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        }
+        Pin::new(&mut self.inner).poll_shutdown(cx)
+    }
+}
+
+impl FileWrite for MultipartUploadWriter {}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll, Wake, Waker};
+
+    use super::*;
+
+    /// A [`FileWrite`] that returns `Poll::Pending` from `poll_write` exactly once, to exercise
+    /// the `AsyncWrite` contract requiring a `Pending` call to be retried with the same `buf`.
+    struct FlakyWriter {
+        pending_once: bool,
+        written: Arc<Mutex<Vec<u8>>>,
+    }
+
+    impl AsyncWrite for FlakyWriter {
+        fn poll_write(
+            mut self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            if self.pending_once {
+                self.pending_once = false;
+                return Poll::Pending;
+            }
+            self.written.lock().unwrap().extend_from_slice(buf);
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl FileWrite for FlakyWriter {}
+
+    struct NoopWake;
+    impl Wake for NoopWake {
+        fn wake(self: Arc<Self>) {}
+    }
+
+    #[test]
+    fn poll_write_does_not_duplicate_buffered_bytes_on_pending_retry() {
+        let waker = Waker::from(Arc::new(NoopWake));
+        let mut cx = Context::from_waker(&waker);
+
+        let written = Arc::new(Mutex::new(Vec::new()));
+        let inner = FlakyWriter {
+            pending_once: true,
+            written: written.clone(),
+        };
+        let mut writer = MultipartUploadWriter::new(Box::new(inner), 4);
+
+        // Fill the buffer up to `buffer_size` without triggering a flush yet.
+        let first = Pin::new(&mut writer).poll_write(&mut cx, b"abcd");
+        assert!(matches!(first, Poll::Ready(Ok(4))));
+
+        // The buffer is now full, so this write must flush first. The inner writer reports
+        // `Pending`, so `buf` must not be appended to `self.buffer` yet.
+        let second = Pin::new(&mut writer).poll_write(&mut cx, b"efgh");
+        assert!(matches!(second, Poll::Pending));
+        assert_eq!(writer.buffer, b"abcd");
+
+        // Retried with the same `buf`, per the `AsyncWrite` contract. The inner writer now
+        // succeeds, so the original bytes are flushed exactly once and `buf` is then buffered.
+        let third = Pin::new(&mut writer).poll_write(&mut cx, b"efgh");
+        assert!(matches!(third, Poll::Ready(Ok(4))));
+        assert_eq!(*written.lock().unwrap(), b"abcd");
+        assert_eq!(writer.buffer, b"efgh");
+    }
+
+    #[derive(Debug, PartialEq, Eq)]
+    enum UploadOutcome {
+        InProgress,
+        Completed,
+        Aborted,
+    }
+
+    /// A [`FileWrite`] standing in for an object-store multipart upload: it tracks whether it was
+    /// ever shut down and, like a real multipart upload, aborts itself on drop if it wasn't.
+    struct TrackedUpload {
+        outcome: Arc<Mutex<UploadOutcome>>,
+    }
+
+    impl AsyncWrite for TrackedUpload {
+        fn poll_write(
+            self: Pin<&mut Self>,
+            _cx: &mut Context<'_>,
+            buf: &[u8],
+        ) -> Poll<io::Result<usize>> {
+            Poll::Ready(Ok(buf.len()))
+        }
+
+        fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+            *self.outcome.lock().unwrap() = UploadOutcome::Completed;
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    impl FileWrite for TrackedUpload {}
+
+    impl Drop for TrackedUpload {
+        fn drop(&mut self) {
+            let mut outcome = self.outcome.lock().unwrap();
+            if *outcome == UploadOutcome::InProgress {
+                *outcome = UploadOutcome::Aborted;
+            }
+        }
+    }
+
+    #[test]
+    fn a_close_that_never_reaches_shutdown_aborts_instead_of_completing() {
+        let waker = Waker::from(Arc::new(NoopWake));
+        let mut cx = Context::from_waker(&waker);
+
+        let outcome = Arc::new(Mutex::new(UploadOutcome::InProgress));
+        let mut writer = MultipartUploadWriter::new(
+            Box::new(TrackedUpload {
+                outcome: outcome.clone(),
+            }),
+            4,
+        );
+        assert!(matches!(
+            Pin::new(&mut writer).poll_write(&mut cx, b"ab"),
+            Poll::Ready(Ok(2))
+        ));
+
+        // Simulate `AsyncArrowWriter::close()` failing before it ever calls `poll_shutdown` on
+        // this writer (e.g. a footer-encoding error): the writer is simply dropped with data
+        // still buffered. The inner upload must end up aborted, not completed - otherwise a
+        // failed close would leak a part instead of cleaning it up.
+        drop(writer);
+        assert_eq!(*outcome.lock().unwrap(), UploadOutcome::Aborted);
+    }
+
+    #[test]
+    fn a_successful_shutdown_flushes_remaining_bytes_before_completing() {
+        let waker = Waker::from(Arc::new(NoopWake));
+        let mut cx = Context::from_waker(&waker);
+
+        let outcome = Arc::new(Mutex::new(UploadOutcome::InProgress));
+        let mut writer = MultipartUploadWriter::new(
+            Box::new(TrackedUpload {
+                outcome: outcome.clone(),
+            }),
+            4,
+        );
+        assert!(matches!(
+            Pin::new(&mut writer).poll_write(&mut cx, b"ab"),
+            Poll::Ready(Ok(2))
+        ));
+
+        assert!(matches!(
+            Pin::new(&mut writer).poll_shutdown(&mut cx),
+            Poll::Ready(Ok(()))
+        ));
+        assert!(writer.buffer.is_empty());
+        assert_eq!(*outcome.lock().unwrap(), UploadOutcome::Completed);
+    }
+}
@@ -0,0 +1,274 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The parquet file writer.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use arrow_array::RecordBatch;
+use parquet::arrow::AsyncArrowWriter;
+use parquet::file::properties::WriterProperties;
+
+use super::byte_counting_writer::ByteCountingWriter;
+use super::location_generator::{FileNameGenerator, LocationGenerator};
+use super::multipart_upload_writer::MultipartUploadWriter;
+use super::{FileWriter, FileWriterBuilder};
+use crate::io::{FileIO, FileWrite};
+use crate::spec::{DataFileBuilder, DataFileFormat, Schema};
+use crate::writer::CurrentFileStatus;
+use crate::Result;
+
+/// The default buffer size used by [`ParquetWriterBuilder::with_streaming_upload`], 8MB.
+pub const DEFAULT_STREAMING_UPLOAD_BUFFER_SIZE: usize = 8 * 1024 * 1024;
+
+/// A builder for [`ParquetWriter`].
+#[derive(Clone, Debug)]
+pub struct ParquetWriterBuilder<L: LocationGenerator, F: FileNameGenerator> {
+    props: WriterProperties,
+    schema: Arc<Schema>,
+    file_io: FileIO,
+    location_generator: L,
+    file_name_generator: F,
+    streaming_upload_buffer_size: Option<usize>,
+}
+
+impl<L: LocationGenerator, F: FileNameGenerator> ParquetWriterBuilder<L, F> {
+    /// Create a new `ParquetWriterBuilder`.
+    pub fn new(
+        props: WriterProperties,
+        schema: Arc<Schema>,
+        file_io: FileIO,
+        location_generator: L,
+        file_name_generator: F,
+    ) -> Self {
+        Self {
+            props,
+            schema,
+            file_io,
+            location_generator,
+            file_name_generator,
+            streaming_upload_buffer_size: None,
+        }
+    }
+
+    /// Write through a bounded in-memory buffer of `buffer_size` bytes into an object-store
+    /// multipart upload, flushing a part each time the buffer fills up, instead of buffering the
+    /// whole file in memory before handing it to [`FileIO`]. This bounds memory use to
+    /// `O(buffer_size)` regardless of the written file's size, at the cost of one multipart part
+    /// upload per `buffer_size` bytes.
+    pub fn with_streaming_upload(mut self, buffer_size: usize) -> Self {
+        self.streaming_upload_buffer_size = Some(buffer_size);
+        self
+    }
+
+    /// Like [`Self::with_streaming_upload`], but buffering [`DEFAULT_STREAMING_UPLOAD_BUFFER_SIZE`]
+    /// bytes instead of a caller-chosen size.
+    pub fn with_streaming_upload_default(self) -> Self {
+        self.with_streaming_upload(DEFAULT_STREAMING_UPLOAD_BUFFER_SIZE)
+    }
+}
+
+#[async_trait::async_trait]
+impl<L: LocationGenerator, F: FileNameGenerator> FileWriterBuilder for ParquetWriterBuilder<L, F> {
+    type R = ParquetWriter;
+
+    async fn build(self) -> Result<Self::R> {
+        let out_file = self.file_io.new_output(
+            self.location_generator
+                .generate_location(&self.file_name_generator.generate_file_name()),
+        )?;
+        let writer: Box<dyn FileWrite> = match self.streaming_upload_buffer_size {
+            Some(buffer_size) => Box::new(MultipartUploadWriter::new(
+                out_file.writer().await?,
+                buffer_size,
+            )),
+            None => out_file.writer().await?,
+        };
+        // `AsyncArrowWriter::bytes_written` only counts row groups flushed so far - it never
+        // accounts for the final buffered row group or the footer that `close()` writes out. Wrap
+        // the sink itself so every byte actually written, including during `close()`, is counted,
+        // and the total survives `close()` consuming the inner writer.
+        let written_size = Arc::new(AtomicUsize::new(0));
+        let writer: Box<dyn FileWrite> =
+            Box::new(ByteCountingWriter::new(writer, written_size.clone()));
+        let arrow_schema = Arc::new(self.schema.as_ref().try_into()?);
+        let inner_writer = AsyncArrowWriter::try_new(writer, arrow_schema, Some(self.props))?;
+
+        Ok(ParquetWriter {
+            file_path: out_file.location().to_string(),
+            inner_writer,
+            written_size,
+            current_row_num: 0,
+        })
+    }
+}
+
+/// A file writer that writes record batches into a single parquet file.
+pub struct ParquetWriter {
+    file_path: String,
+    inner_writer: AsyncArrowWriter<Box<dyn FileWrite>>,
+    written_size: Arc<AtomicUsize>,
+    current_row_num: usize,
+}
+
+#[async_trait::async_trait]
+impl FileWriter for ParquetWriter {
+    async fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        self.current_row_num += batch.num_rows();
+        self.inner_writer.write(batch).await?;
+        Ok(())
+    }
+
+    async fn close(self) -> Result<Vec<DataFileBuilder>> {
+        let metadata = self.inner_writer.close().await?;
+
+        Ok(vec![DataFileBuilder::new()
+            .file_path(self.file_path)
+            .file_format(DataFileFormat::Parquet)
+            .record_count(metadata.num_rows as u64)
+            .file_size_in_bytes(self.written_size.load(Ordering::Relaxed) as u64)
+            .clone()])
+    }
+}
+
+impl CurrentFileStatus for ParquetWriter {
+    fn current_file_path(&self) -> String {
+        self.file_path.clone()
+    }
+
+    fn current_row_num(&self) -> usize {
+        self.current_row_num
+    }
+
+    fn current_written_size(&self) -> usize {
+        self.written_size.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::fs;
+
+    use arrow_array::Int32Array;
+    use arrow_schema::{DataType, Field, Schema as ArrowSchema};
+    use parquet::arrow::PARQUET_FIELD_ID_META_KEY;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::arrow::arrow_schema_to_schema;
+    use crate::io::FileIOBuilder;
+    use crate::writer::base_writer::data_file_writer::DataFileWriterBuilder;
+    use crate::writer::file_writer::location_generator::{FileNameGenerator, LocationGenerator};
+    use crate::writer::{IcebergWriter, IcebergWriterBuilder};
+
+    #[derive(Clone)]
+    struct TestLocationGenerator(String);
+
+    impl LocationGenerator for TestLocationGenerator {
+        fn generate_location(&self, file_name: &str) -> String {
+            format!("{}/{}", self.0, file_name)
+        }
+    }
+
+    #[derive(Clone)]
+    struct TestFileNameGenerator;
+
+    impl FileNameGenerator for TestFileNameGenerator {
+        fn generate_file_name(&self) -> String {
+            "test.parquet".to_string()
+        }
+    }
+
+    fn int_schema_and_batch(values: Vec<i32>) -> (Arc<Schema>, RecordBatch) {
+        let arrow_schema = Arc::new(ArrowSchema::new(vec![Field::new(
+            "id",
+            DataType::Int32,
+            false,
+        )
+        .with_metadata(HashMap::from_iter([(
+            PARQUET_FIELD_ID_META_KEY.to_owned(),
+            "1".to_owned(),
+        )]))]));
+        let schema = Arc::new(arrow_schema_to_schema(&arrow_schema).unwrap());
+        let batch =
+            RecordBatch::try_new(arrow_schema, vec![Arc::new(Int32Array::from(values))]).unwrap();
+        (schema, batch)
+    }
+
+    #[tokio::test]
+    async fn file_size_in_bytes_matches_the_file_actually_written_to_disk() {
+        let tmp_dir = TempDir::new().unwrap();
+        let file_io = FileIOBuilder::new_fs_io().build().unwrap();
+        let (schema, batch) = int_schema_and_batch(vec![1, 2, 3]);
+
+        let writer_builder = ParquetWriterBuilder::new(
+            WriterProperties::builder().build(),
+            schema,
+            file_io.clone(),
+            TestLocationGenerator(tmp_dir.path().display().to_string()),
+            TestFileNameGenerator,
+        );
+        let mut writer = DataFileWriterBuilder::new(writer_builder, None)
+            .build()
+            .await
+            .unwrap();
+        writer.write(batch.clone()).await.unwrap();
+        let data_files = writer.close().await.unwrap();
+
+        assert_eq!(data_files.len(), 1);
+        let data_file = &data_files[0];
+
+        let on_disk_size = fs::metadata(data_file.file_path()).unwrap().len();
+        assert_eq!(data_file.file_size_in_bytes(), on_disk_size);
+
+        crate::writer::tests::check_parquet_data_file(&file_io, data_file, &batch).await;
+    }
+
+    #[tokio::test]
+    async fn with_streaming_upload_round_trips_a_file_written_across_many_parts() {
+        let tmp_dir = TempDir::new().unwrap();
+        let file_io = FileIOBuilder::new_fs_io().build().unwrap();
+        // One row is a handful of bytes, so a tiny buffer forces `MultipartUploadWriter` to
+        // flush several parts over the course of writing this batch, instead of buffering the
+        // whole file in memory the way the non-streaming path does.
+        let (schema, batch) = int_schema_and_batch((0..64).collect());
+
+        let writer_builder = ParquetWriterBuilder::new(
+            WriterProperties::builder().build(),
+            schema,
+            file_io.clone(),
+            TestLocationGenerator(tmp_dir.path().display().to_string()),
+            TestFileNameGenerator,
+        )
+        .with_streaming_upload(16);
+        let mut writer = DataFileWriterBuilder::new(writer_builder, None)
+            .build()
+            .await
+            .unwrap();
+        writer.write(batch.clone()).await.unwrap();
+        let data_files = writer.close().await.unwrap();
+
+        assert_eq!(data_files.len(), 1);
+        let data_file = &data_files[0];
+        let on_disk_size = fs::metadata(data_file.file_path()).unwrap().len();
+        assert_eq!(data_file.file_size_in_bytes(), on_disk_size);
+
+        crate::writer::tests::check_parquet_data_file(&file_io, data_file, &batch).await;
+    }
+}
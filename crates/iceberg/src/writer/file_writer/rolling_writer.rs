@@ -0,0 +1,326 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! This module provides the `RollingFileWriter`, which rolls over to a new physical file once a
+//! size or row count threshold is crossed.
+
+use arrow_array::RecordBatch;
+
+use super::{FileWriter, FileWriterBuilder};
+use crate::spec::DataFileBuilder;
+use crate::writer::CurrentFileStatus;
+use crate::Result;
+
+/// The default target size of a data file, 512MB.
+pub const DEFAULT_TARGET_FILE_SIZE_BYTES: usize = 512 * 1024 * 1024;
+
+/// Builder for [`RollingFileWriter`].
+#[derive(Clone)]
+pub struct RollingFileWriterBuilder<B: FileWriterBuilder> {
+    inner_builder: B,
+    target_file_size_bytes: usize,
+    target_row_count: Option<usize>,
+}
+
+impl<B: FileWriterBuilder> RollingFileWriterBuilder<B> {
+    /// Create a new `RollingFileWriterBuilder` that rolls over once
+    /// [`DEFAULT_TARGET_FILE_SIZE_BYTES`] is crossed.
+    pub fn new(inner_builder: B) -> Self {
+        Self {
+            inner_builder,
+            target_file_size_bytes: DEFAULT_TARGET_FILE_SIZE_BYTES,
+            target_row_count: None,
+        }
+    }
+
+    /// Set the target file size, in bytes, at which the writer rolls over to a new file.
+    pub fn with_target_file_size_bytes(mut self, target_file_size_bytes: usize) -> Self {
+        self.target_file_size_bytes = target_file_size_bytes;
+        self
+    }
+
+    /// Additionally roll over to a new file once the current file holds `target_row_count` rows.
+    pub fn with_target_row_count(mut self, target_row_count: usize) -> Self {
+        self.target_row_count = Some(target_row_count);
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl<B: FileWriterBuilder> FileWriterBuilder for RollingFileWriterBuilder<B>
+where
+    B::R: CurrentFileStatus,
+{
+    type R = RollingFileWriter<B>;
+
+    async fn build(self) -> Result<Self::R> {
+        Ok(RollingFileWriter {
+            inner_builder: self.inner_builder.clone(),
+            target_file_size_bytes: self.target_file_size_bytes,
+            target_row_count: self.target_row_count,
+            current_writer: Some(self.inner_builder.build().await?),
+            closed_data_files: Vec::new(),
+        })
+    }
+}
+
+/// A [`FileWriter`] that transparently rolls over to a new physical file, produced by the
+/// wrapped [`FileWriterBuilder`], once `target_file_size_bytes` (or, if set,
+/// `target_row_count`) is crossed. This lets a single logical file writer emit many
+/// appropriately sized files instead of one unbounded one.
+pub struct RollingFileWriter<B: FileWriterBuilder> {
+    inner_builder: B,
+    target_file_size_bytes: usize,
+    target_row_count: Option<usize>,
+    current_writer: Option<B::R>,
+    closed_data_files: Vec<DataFileBuilder>,
+}
+
+impl<B: FileWriterBuilder> RollingFileWriter<B>
+where
+    B::R: CurrentFileStatus,
+{
+    fn should_roll(&self) -> bool {
+        let Some(current_writer) = self.current_writer.as_ref() else {
+            return false;
+        };
+        current_writer.current_written_size() >= self.target_file_size_bytes
+            || self
+                .target_row_count
+                .is_some_and(|target| current_writer.current_row_num() >= target)
+    }
+
+    async fn roll(&mut self) -> Result<()> {
+        // Build the replacement writer and swap it in *before* closing the old one, so a failure
+        // on either step never leaves `current_writer` as `None` - otherwise a transient error
+        // during rollover would permanently poison this writer, turning every subsequent
+        // `write`/`close` call's `.expect(..)` into a panic instead of a recoverable `Result::Err`.
+        let new_writer = self.inner_builder.clone().build().await?;
+        if let Some(writer) = self.current_writer.replace(new_writer) {
+            self.closed_data_files.extend(writer.close().await?);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl<B: FileWriterBuilder> FileWriter for RollingFileWriter<B>
+where
+    B::R: CurrentFileStatus,
+{
+    async fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+        self.current_writer
+            .as_mut()
+            .expect("RollingFileWriter has been closed")
+            .write(batch)
+            .await?;
+
+        if self.should_roll() {
+            self.roll().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn close(self) -> Result<Vec<DataFileBuilder>> {
+        let mut data_files = self.closed_data_files;
+        if let Some(writer) = self.current_writer {
+            data_files.extend(writer.close().await?);
+        }
+        Ok(data_files)
+    }
+}
+
+impl<B: FileWriterBuilder> CurrentFileStatus for RollingFileWriter<B>
+where
+    B::R: CurrentFileStatus,
+{
+    fn current_file_path(&self) -> String {
+        self.current_writer
+            .as_ref()
+            .expect("RollingFileWriter has been closed")
+            .current_file_path()
+    }
+
+    fn current_row_num(&self) -> usize {
+        self.current_writer
+            .as_ref()
+            .expect("RollingFileWriter has been closed")
+            .current_row_num()
+    }
+
+    fn current_written_size(&self) -> usize {
+        self.current_writer
+            .as_ref()
+            .expect("RollingFileWriter has been closed")
+            .current_written_size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use arrow_array::Int32Array;
+    use arrow_schema::{DataType, Field, Schema};
+
+    use super::*;
+    use crate::{Error, ErrorKind};
+
+    fn one_row_batch() -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![Field::new("id", DataType::Int32, false)]));
+        RecordBatch::try_new(schema, vec![Arc::new(Int32Array::from(vec![1]))]).unwrap()
+    }
+
+    /// Bytes `MockWriter` pretends each row costs, so tests can cross
+    /// `target_file_size_bytes` without needing real Parquet output.
+    const MOCK_BYTES_PER_ROW: usize = 8;
+
+    struct MockWriter {
+        id: usize,
+        row_num: usize,
+        written_size: usize,
+    }
+
+    #[async_trait::async_trait]
+    impl FileWriter for MockWriter {
+        async fn write(&mut self, batch: &RecordBatch) -> Result<()> {
+            self.row_num += batch.num_rows();
+            self.written_size += batch.num_rows() * MOCK_BYTES_PER_ROW;
+            Ok(())
+        }
+
+        async fn close(self) -> Result<Vec<DataFileBuilder>> {
+            Ok(vec![DataFileBuilder::new()
+                .file_path(format!("file-{}", self.id))
+                .file_format(crate::spec::DataFileFormat::Parquet)
+                .record_count(self.row_num as u64)
+                .file_size_in_bytes(self.written_size as u64)
+                .clone()])
+        }
+    }
+
+    impl CurrentFileStatus for MockWriter {
+        fn current_file_path(&self) -> String {
+            format!("file-{}", self.id)
+        }
+
+        fn current_row_num(&self) -> usize {
+            self.row_num
+        }
+
+        fn current_written_size(&self) -> usize {
+            self.written_size
+        }
+    }
+
+    /// A [`FileWriterBuilder`] whose `build` can be made to fail exactly once, to exercise
+    /// [`RollingFileWriter::roll`]'s error recovery.
+    #[derive(Clone)]
+    struct MockWriterBuilder {
+        next_id: Arc<AtomicUsize>,
+        fail_next_build: Arc<AtomicBool>,
+    }
+
+    impl MockWriterBuilder {
+        fn new() -> Self {
+            Self {
+                next_id: Arc::new(AtomicUsize::new(0)),
+                fail_next_build: Arc::new(AtomicBool::new(false)),
+            }
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl FileWriterBuilder for MockWriterBuilder {
+        type R = MockWriter;
+
+        async fn build(self) -> Result<Self::R> {
+            if self.fail_next_build.swap(false, Ordering::SeqCst) {
+                return Err(Error::new(ErrorKind::Unexpected, "injected build failure"));
+            }
+            Ok(MockWriter {
+                id: self.next_id.fetch_add(1, Ordering::SeqCst),
+                row_num: 0,
+                written_size: 0,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn rolls_over_once_target_row_count_is_crossed() {
+        let mut writer = RollingFileWriterBuilder::new(MockWriterBuilder::new())
+            .with_target_row_count(2)
+            .build()
+            .await
+            .unwrap();
+
+        let batch = one_row_batch();
+        writer.write(&batch).await.unwrap();
+        assert_eq!(writer.current_file_path(), "file-0");
+
+        writer.write(&batch).await.unwrap();
+        assert_eq!(writer.current_file_path(), "file-1");
+
+        let data_files = writer.close().await.unwrap();
+        assert_eq!(data_files.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn rolls_over_once_target_file_size_bytes_is_crossed() {
+        // No `target_row_count` set, so `target_file_size_bytes` - the default rollover trigger -
+        // is the only thing that can cause a roll here.
+        let mut writer = RollingFileWriterBuilder::new(MockWriterBuilder::new())
+            .with_target_file_size_bytes(MOCK_BYTES_PER_ROW * 2)
+            .build()
+            .await
+            .unwrap();
+
+        let batch = one_row_batch();
+        writer.write(&batch).await.unwrap();
+        assert_eq!(writer.current_file_path(), "file-0");
+
+        writer.write(&batch).await.unwrap();
+        assert_eq!(writer.current_file_path(), "file-1");
+
+        let data_files = writer.close().await.unwrap();
+        assert_eq!(data_files.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn failed_roll_leaves_the_writer_usable() {
+        let inner_builder = MockWriterBuilder::new();
+        let fail_next_build = inner_builder.fail_next_build.clone();
+        let mut writer = RollingFileWriterBuilder::new(inner_builder)
+            .with_target_row_count(1)
+            .build()
+            .await
+            .unwrap();
+
+        let batch = one_row_batch();
+        fail_next_build.store(true, Ordering::SeqCst);
+        // The write itself succeeds, but the roll it triggers fails to build a replacement
+        // writer.
+        assert!(writer.write(&batch).await.is_err());
+
+        // `current_writer` must still be populated, so this neither panics nor errors out just
+        // because the previous roll failed.
+        writer.write(&batch).await.unwrap();
+        assert!(writer.close().await.is_ok());
+    }
+}
@@ -83,6 +83,20 @@
 pub mod base_writer;
 pub mod file_writer;
 
+/// Derives [`base_writer::typed_writer::IcebergRecord`] and
+/// [`base_writer::typed_writer::IcebergRecordField`] for a struct, so it can be written through a
+/// [`base_writer::typed_writer::TypedWriter`] without hand-building Arrow arrays.
+pub use iceberg_derive::IcebergRecordWriter;
+
+/// Re-exports of third-party crates referenced by the code `#[derive(IcebergRecordWriter)]`
+/// generates. `iceberg-derive` can't depend on `iceberg` itself (that would be a cycle), so the
+/// generated code reaches these through `::iceberg::writer::__private::*` instead, relying on
+/// `iceberg` already being a direct dependency of anything that uses the derive macro.
+#[doc(hidden)]
+pub mod __private {
+    pub use {arrow_array, arrow_buffer, arrow_schema, parquet};
+}
+
 use arrow_array::RecordBatch;
 
 use crate::spec::DataFile;